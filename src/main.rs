@@ -1,11 +1,17 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::env;
 use std::fs::File;
+use std::hash::{Hash, Hasher};
 use std::io;
 use std::ops::Index;
 
 use csv;
 
 use sqlparser::ast::{
-    BinaryOperator, Expr, Query, SelectItem, SetExpr, Statement, TableFactor, Value as Literal,
+    BinaryOperator, Expr, Function, FunctionArg, FunctionArgExpr, JoinConstraint, JoinOperator,
+    OrderByExpr, Query, SelectItem, SetExpr, Statement, TableFactor, TableWithJoins,
+    Value as Literal,
 };
 use sqlparser::dialect::GenericDialect;
 use sqlparser::parser::Parser;
@@ -15,223 +21,1397 @@ enum Value {
     String(String),
     Boolean(bool),
     Integer(i64),
+    Float(f64),
+    Null,
 }
 
-trait Relation: Iterator<Item = Vec<Value>> {
-    fn attributes(&mut self) -> Vec<String>;
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::String(a), Value::String(b)) => a == b,
+            (Value::Boolean(a), Value::Boolean(b)) => a == b,
+            (Value::Integer(a), Value::Integer(b)) => a == b,
+            (Value::Float(a), Value::Float(b)) => a.to_bits() == b.to_bits(),
+            (Value::Null, Value::Null) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Value {}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+
+        match self {
+            Value::String(s) => s.hash(state),
+            Value::Boolean(b) => b.hash(state),
+            Value::Integer(i) => i.hash(state),
+            Value::Float(f) => f.to_bits().hash(state),
+            Value::Null => {}
+        }
+    }
+}
+
+fn value_to_string(value: Value) -> String {
+    match value {
+        Value::String(s) => s,
+        Value::Boolean(b) => b.to_string(),
+        Value::Integer(i) => i.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Null => String::new(),
+    }
+}
+
+/// Up to `BATCH_SIZE` rows of one column each, rather than one row of all columns: operators
+/// evaluate a predicate or pick columns across a whole batch at once, amortizing dispatch over
+/// contiguous typed slices instead of paying per-row overhead.
+const BATCH_SIZE: usize = 1024;
+
+/// Each variant pairs its typed values with a parallel validity bitmap (`true` = non-null), so a
+/// column can carry `Value::Null` entries (e.g. unmatched `LEFT JOIN` rows) without losing its
+/// type: the typed slot at a null index is a meaningless placeholder and must never be read
+/// without first checking the bitmap.
+#[derive(Clone)]
+enum Column {
+    Ints(Vec<i64>, Vec<bool>),
+    Floats(Vec<f64>, Vec<bool>),
+    Bools(Vec<bool>, Vec<bool>),
+    Strings(Vec<String>, Vec<bool>),
+}
+
+fn compact_vec<T: Clone>(values: &[T], keep: &[bool]) -> Vec<T> {
+    values
+        .iter()
+        .zip(keep)
+        .filter(|(_, keep)| **keep)
+        .map(|(value, _)| value.clone())
+        .collect()
+}
+
+impl Column {
+    fn len(&self) -> usize {
+        match self {
+            Column::Ints(values, _) => values.len(),
+            Column::Floats(values, _) => values.len(),
+            Column::Bools(values, _) => values.len(),
+            Column::Strings(values, _) => values.len(),
+        }
+    }
+
+    fn value_at(&self, index: usize) -> Value {
+        match self {
+            Column::Ints(values, valid) => {
+                if valid[index] {
+                    Value::Integer(values[index])
+                } else {
+                    Value::Null
+                }
+            }
+            Column::Floats(values, valid) => {
+                if valid[index] {
+                    Value::Float(values[index])
+                } else {
+                    Value::Null
+                }
+            }
+            Column::Bools(values, valid) => {
+                if valid[index] {
+                    Value::Boolean(values[index])
+                } else {
+                    Value::Null
+                }
+            }
+            Column::Strings(values, valid) => {
+                if valid[index] {
+                    Value::String(values[index].clone())
+                } else {
+                    Value::Null
+                }
+            }
+        }
+    }
+
+    fn compact(&self, keep: &[bool]) -> Column {
+        match self {
+            Column::Ints(values, valid) => {
+                Column::Ints(compact_vec(values, keep), compact_vec(valid, keep))
+            }
+            Column::Floats(values, valid) => {
+                Column::Floats(compact_vec(values, keep), compact_vec(valid, keep))
+            }
+            Column::Bools(values, valid) => {
+                Column::Bools(compact_vec(values, keep), compact_vec(valid, keep))
+            }
+            Column::Strings(values, valid) => {
+                Column::Strings(compact_vec(values, keep), compact_vec(valid, keep))
+            }
+        }
+    }
+}
+
+/// Builds a `Column` from row-order values, keeping a parallel validity bitmap so `Value::Null`
+/// entries (e.g. unmatched `LEFT JOIN` rows) don't force the whole column into `Strings` and lose
+/// their type, and so a column mixing `Integer` and `Float` cells promotes to `Floats` rather than
+/// degrading to text. Only genuinely heterogeneous columns (e.g. strings next to booleans) fall
+/// back to stringifying every non-null cell.
+fn column_from_values(values: Vec<Value>) -> Column {
+    let valid: Vec<bool> = values.iter().map(|value| !matches!(value, Value::Null)).collect();
+
+    if values.iter().all(|value| matches!(value, Value::Integer(_) | Value::Null)) {
+        return Column::Ints(
+            values
+                .into_iter()
+                .map(|value| match value {
+                    Value::Integer(i) => i,
+                    Value::Null => 0,
+                    _ => unreachable!(),
+                })
+                .collect(),
+            valid,
+        );
+    }
+
+    if values
+        .iter()
+        .all(|value| matches!(value, Value::Integer(_) | Value::Float(_) | Value::Null))
+    {
+        return Column::Floats(
+            values
+                .into_iter()
+                .map(|value| match value {
+                    Value::Integer(i) => i as f64,
+                    Value::Float(f) => f,
+                    Value::Null => 0.0,
+                    _ => unreachable!(),
+                })
+                .collect(),
+            valid,
+        );
+    }
+
+    if values.iter().all(|value| matches!(value, Value::Boolean(_) | Value::Null)) {
+        return Column::Bools(
+            values
+                .into_iter()
+                .map(|value| match value {
+                    Value::Boolean(b) => b,
+                    Value::Null => false,
+                    _ => unreachable!(),
+                })
+                .collect(),
+            valid,
+        );
+    }
+
+    Column::Strings(
+        values
+            .into_iter()
+            .map(|value| match value {
+                Value::Null => String::new(),
+                value => value_to_string(value),
+            })
+            .collect(),
+        valid,
+    )
+}
+
+struct RecordBatch {
+    columns: Vec<Column>,
+}
+
+impl RecordBatch {
+    fn num_rows(&self) -> usize {
+        self.columns.first().map(Column::len).unwrap_or(0)
+    }
+
+    fn compact(&self, keep: &[bool]) -> RecordBatch {
+        RecordBatch {
+            columns: self.columns.iter().map(|column| column.compact(keep)).collect(),
+        }
+    }
+}
+
+fn rows_to_batch(rows: Vec<Vec<Value>>) -> RecordBatch {
+    let num_columns = rows.first().map(Vec::len).unwrap_or(0);
+
+    let columns = (0..num_columns)
+        .map(|column_index| {
+            column_from_values(rows.iter().map(|row| row[column_index].clone()).collect())
+        })
+        .collect();
+
+    RecordBatch { columns }
+}
+
+fn batch_to_rows(batch: RecordBatch) -> Vec<Vec<Value>> {
+    let num_rows = batch.num_rows();
+
+    (0..num_rows)
+        .map(|row_index| {
+            batch
+                .columns
+                .iter()
+                .map(|column| column.value_at(row_index))
+                .collect()
+        })
+        .collect()
+}
+
+/// A relation's column, tagged with the table (or alias) it was scanned from when that's known.
+/// Carrying the table alongside the bare name lets a qualified lookup like `orders.id` pick the
+/// right side of a JOIN instead of the first column anywhere with a matching bare name; columns
+/// synthesized by a `Projection`/`Aggregation` (aliases, computed expressions) carry `table: None`
+/// since they no longer trace back to a single base table.
+#[derive(Clone)]
+struct Attribute {
+    table: Option<String>,
+    name: String,
+}
+
+trait Relation: Iterator<Item = RecordBatch> {
+    fn attributes(&mut self) -> Vec<Attribute>;
 }
 
 struct SequentialScan {
     reader: csv::Reader<File>,
+    table_name: String,
 }
 
 impl SequentialScan {
-    pub fn from_path(path: &String) -> Self {
+    pub fn from_path(path: &String, table_name: String) -> Self {
         let reader = csv::Reader::from_path(path)
             .expect(format!("Could not create CSV-reader from path: {}", path).as_str());
 
-        Self { reader }
+        Self { reader, table_name }
     }
 }
 
 impl Iterator for SequentialScan {
-    type Item = Vec<Value>;
+    type Item = RecordBatch;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.reader.records().next() {
-            Some(result) => match result {
+        let mut rows: Vec<Vec<Value>> = Vec::new();
+
+        for result in self.reader.records().take(BATCH_SIZE) {
+            match result {
                 Ok(record) => {
-                    let item = Vec::from_iter(record.iter().map(|s| s.to_owned()).map(|s| {
-                        if let Ok(boolean) = s.parse::<bool>() {
+                    let row = Vec::from_iter(record.iter().map(|s| s.to_owned()).map(|s| {
+                        if s.is_empty() {
+                            Value::Null
+                        } else if let Ok(boolean) = s.parse::<bool>() {
                             Value::Boolean(boolean)
                         } else if let Ok(integer) = s.parse::<i64>() {
                             Value::Integer(integer)
+                        } else if let Ok(float) = s.parse::<f64>() {
+                            Value::Float(float)
                         } else {
                             Value::String(s)
                         }
                     }));
-                    Some(item)
+                    rows.push(row);
                 }
                 Err(err) => {
                     eprintln!("{err}");
-                    None
+                    break;
                 }
-            },
-            None => None,
+            }
         }
+
+        if rows.is_empty() {
+            return None;
+        }
+
+        Some(rows_to_batch(rows))
     }
 }
 
 impl Relation for SequentialScan {
-    fn attributes(&mut self) -> Vec<String> {
+    fn attributes(&mut self) -> Vec<Attribute> {
         let headers = self
             .reader
             .headers()
             .expect("Could not get headers from CSV-reader.");
 
-        Vec::from_iter(headers.iter().map(|s| s.to_owned()))
+        headers
+            .iter()
+            .map(|name| Attribute {
+                table: Some(self.table_name.clone()),
+                name: name.to_owned(),
+            })
+            .collect()
+    }
+}
+
+/// An `Expr` lowered against a fixed attribute list: identifiers become column indices, so
+/// evaluation indexes straight into a `RecordBatch`'s columns with no string lookups or clones of
+/// the AST.
+/// Names are resolved once, at plan time, by `compile_expr`; execution never sees them again.
+enum CompiledExpr {
+    Column(usize),
+    Const(Value),
+    Binary(Box<CompiledExpr>, BinaryOperator, Box<CompiledExpr>),
+    IsNull(Box<CompiledExpr>, bool),
+}
+
+fn literal_to_value(literal: &Literal) -> Value {
+    match literal {
+        Literal::Boolean(b) => Value::Boolean(*b),
+        Literal::DoubleQuotedString(s) | Literal::SingleQuotedString(s) => Value::String(s.clone()),
+        Literal::Number(s, _) => {
+            if s.contains('.') {
+                Value::Float(s.parse::<f64>().expect("Could not parse number into f64."))
+            } else {
+                Value::Integer(s.parse::<i64>().expect("Could not parse number into i64."))
+            }
+        }
+        _ => unimplemented!(),
+    }
+}
+
+fn compile_expr(expr: &Expr, attributes: &Vec<Attribute>) -> CompiledExpr {
+    match expr {
+        Expr::BinaryOp { left, op, right } => CompiledExpr::Binary(
+            Box::new(compile_expr(left, attributes)),
+            op.clone(),
+            Box::new(compile_expr(right, attributes)),
+        ),
+        Expr::Identifier(_) | Expr::CompoundIdentifier(_) => {
+            let parsed = parse_identifier(expr).unwrap();
+
+            CompiledExpr::Column(resolve_attribute(&parsed, attributes))
+        }
+        Expr::Value(literal) => CompiledExpr::Const(literal_to_value(literal)),
+        Expr::IsNull(inner) => CompiledExpr::IsNull(Box::new(compile_expr(inner, attributes)), false),
+        Expr::IsNotNull(inner) => {
+            CompiledExpr::IsNull(Box::new(compile_expr(inner, attributes)), true)
+        }
+        _ => unimplemented!("{expr:?}"),
+    }
+}
+
+/// Three-valued `AND`/`OR`/comparisons: `Value::Null` means "unknown" rather than false, and
+/// propagates per SQL's truth tables instead of being coerced to a definite boolean outright.
+fn eval_binary_op(left_value: Value, op: &BinaryOperator, right_value: Value) -> Value {
+    match op {
+        BinaryOperator::And => match (tristate(&left_value), tristate(&right_value)) {
+            (Some(false), _) | (_, Some(false)) => Value::Boolean(false),
+            (Some(true), Some(true)) => Value::Boolean(true),
+            _ => Value::Null,
+        },
+        BinaryOperator::Or => match (tristate(&left_value), tristate(&right_value)) {
+            (Some(true), _) | (_, Some(true)) => Value::Boolean(true),
+            (Some(false), Some(false)) => Value::Boolean(false),
+            _ => Value::Null,
+        },
+        BinaryOperator::Gt => eval_comparison(left_value, right_value, Ordering::Greater),
+        BinaryOperator::Eq => match (&left_value, &right_value) {
+            (Value::Null, _) | (_, Value::Null) => Value::Null,
+            _ => {
+                let (left_value, right_value) = promote_numeric(left_value, right_value);
+
+                Value::Boolean(left_value == right_value)
+            }
+        },
+        _ => unimplemented!(),
+    }
+}
+
+fn tristate(value: &Value) -> Option<bool> {
+    match value {
+        Value::Null => None,
+        value => Some(eval_value_as_bool(value.clone())),
+    }
+}
+
+/// Comparisons are unknown (`Value::Null`) when either side is `Null`. Otherwise an `Integer`
+/// compared against a `Float` is promoted to `Float` first so e.g. `1 > 0.5` works across types.
+fn eval_comparison(left_value: Value, right_value: Value, wanted: Ordering) -> Value {
+    if matches!(left_value, Value::Null) || matches!(right_value, Value::Null) {
+        return Value::Null;
+    }
+
+    let (left_value, right_value) = promote_numeric(left_value, right_value);
+
+    Value::Boolean(compare_values(&left_value, &right_value) == wanted)
+}
+
+fn promote_numeric(left_value: Value, right_value: Value) -> (Value, Value) {
+    match (&left_value, &right_value) {
+        (Value::Integer(i), Value::Float(_)) => (Value::Float(*i as f64), right_value),
+        (Value::Float(_), Value::Integer(i)) => (left_value, Value::Float(*i as f64)),
+        _ => (left_value, right_value),
+    }
+}
+
+fn eval_compiled_expr_on_batch(expr: &CompiledExpr, batch: &RecordBatch, row_index: usize) -> Value {
+    match expr {
+        CompiledExpr::Column(index) => batch.columns[*index].value_at(row_index),
+        CompiledExpr::Const(value) => value.clone(),
+        CompiledExpr::Binary(left, op, right) => {
+            let left_value = eval_compiled_expr_on_batch(left, batch, row_index);
+            let right_value = eval_compiled_expr_on_batch(right, batch, row_index);
+
+            eval_binary_op(left_value, op, right_value)
+        }
+        CompiledExpr::IsNull(inner, negate) => Value::Boolean(
+            matches!(eval_compiled_expr_on_batch(inner, batch, row_index), Value::Null) != *negate,
+        ),
     }
 }
 
 struct Projection {
-    projected: Vec<SelectItem>,
-    relation: Box<dyn Relation<Item = Vec<Value>>>,
+    columns: Vec<CompiledExpr>,
+    attributes: Vec<Attribute>,
+    relation: Box<dyn Relation>,
+}
+
+impl Projection {
+    pub fn new(projected: Vec<SelectItem>, mut relation: Box<dyn Relation>) -> Self {
+        let relation_attributes = relation.attributes();
+
+        let mut columns = Vec::new();
+        let mut attributes = Vec::new();
+
+        for select_item in projected.iter() {
+            match select_item {
+                SelectItem::Wildcard => {
+                    for (source_position, attribute) in relation_attributes.iter().enumerate() {
+                        columns.push(CompiledExpr::Column(source_position));
+                        attributes.push(attribute.clone());
+                    }
+                }
+                SelectItem::ExprWithAlias { expr, alias } => {
+                    columns.push(compile_expr(expr, &relation_attributes));
+                    attributes.push(Attribute {
+                        table: None,
+                        name: alias.value.clone(),
+                    });
+                }
+                SelectItem::UnnamedExpr(expr) => {
+                    let parsed = parse_identifier(expr).unwrap_or_else(|| unreachable!());
+                    let source_position = resolve_attribute(&parsed, &relation_attributes);
+
+                    columns.push(CompiledExpr::Column(source_position));
+                    attributes.push(relation_attributes[source_position].clone());
+                }
+                _ => unimplemented!(),
+            }
+        }
+
+        Self {
+            columns,
+            attributes,
+            relation,
+        }
+    }
 }
 
 impl Iterator for Projection {
-    type Item = Vec<Value>;
+    type Item = RecordBatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = self.relation.next()?;
+        let num_rows = batch.num_rows();
+
+        let columns = self
+            .columns
+            .iter()
+            .map(|column| match column {
+                CompiledExpr::Column(index) => batch.columns[*index].clone(),
+                _ => column_from_values(
+                    (0..num_rows)
+                        .map(|row_index| eval_compiled_expr_on_batch(column, &batch, row_index))
+                        .collect(),
+                ),
+            })
+            .collect();
+
+        Some(RecordBatch { columns })
+    }
+}
+
+impl Relation for Projection {
+    fn attributes(&mut self) -> Vec<Attribute> {
+        self.attributes.clone()
+    }
+}
+
+struct Selection {
+    selection: CompiledExpr,
+    attributes: Vec<Attribute>,
+    relation: Box<dyn Relation>,
+}
+
+impl Selection {
+    pub fn new(selection: &Expr, mut relation: Box<dyn Relation>) -> Self {
+        let attributes = relation.attributes();
+        let selection = compile_expr(selection, &attributes);
+
+        Self {
+            selection,
+            attributes,
+            relation,
+        }
+    }
+}
+
+impl Iterator for Selection {
+    type Item = RecordBatch;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let relation_attributes: Vec<String> = self.relation.attributes();
+        loop {
+            let batch = self.relation.next()?;
+            let num_rows = batch.num_rows();
+
+            let keep: Vec<bool> = (0..num_rows)
+                .map(|row_index| {
+                    eval_value_as_bool(eval_compiled_expr_on_batch(
+                        &self.selection,
+                        &batch,
+                        row_index,
+                    ))
+                })
+                .collect();
+
+            if !keep.iter().any(|kept| *kept) {
+                continue;
+            }
+
+            return Some(batch.compact(&keep));
+        }
+    }
+}
+
+impl Relation for Selection {
+    fn attributes(&mut self) -> Vec<Attribute> {
+        self.attributes.clone()
+    }
+}
 
-        match self.relation.next() {
-            Some(relation_item) => {
-                let mut item = Vec::new();
+#[derive(Clone, Copy)]
+enum JoinKind {
+    Inner,
+    Left,
+}
 
-                for select_item in self.projected.iter() {
-                    if *select_item == SelectItem::Wildcard {
-                        for attribute in &relation_attributes {
-                            let source_position = relation_attributes
-                                .iter()
-                                .position(|relation_attribute| relation_attribute.eq(attribute))
-                                .unwrap();
+enum JoinAlgorithm {
+    Hash {
+        left_column: usize,
+        table: HashMap<Value, Vec<Vec<Value>>>,
+    },
+    NestedLoop {
+        on: Expr,
+        rows: Vec<Vec<Value>>,
+    },
+}
 
-                            item.push(relation_item.index(source_position).clone());
+struct Join {
+    join_kind: JoinKind,
+    left: Box<dyn Relation>,
+    left_rows: std::vec::IntoIter<Vec<Value>>,
+    left_attributes: Vec<Attribute>,
+    right_attributes: Vec<Attribute>,
+    algorithm: JoinAlgorithm,
+    current_left_row: Option<Vec<Value>>,
+    current_left_matched: bool,
+    pending_right_rows: std::vec::IntoIter<Vec<Value>>,
+}
+
+impl Join {
+    pub fn new(
+        join_kind: JoinKind,
+        on: Expr,
+        mut left: Box<dyn Relation>,
+        mut right: Box<dyn Relation>,
+    ) -> Self {
+        let left_attributes = left.attributes();
+        let right_attributes = right.attributes();
+
+        let algorithm = match equality_join_columns(&on, &left_attributes, &right_attributes) {
+            Some((left_column, right_column)) => {
+                let mut table: HashMap<Value, Vec<Vec<Value>>> = HashMap::new();
+
+                for right_batch in right.by_ref() {
+                    for right_row in batch_to_rows(right_batch) {
+                        // `Value`'s `Eq`/`Hash` treat `Null == Null`, but SQL's `NULL = NULL` is
+                        // unknown, not true, so a NULL join key must never match another NULL key.
+                        // Skipping it here keeps this path's results identical to the nested-loop
+                        // fallback's three-valued `Eq`.
+                        if matches!(right_row[right_column], Value::Null) {
+                            continue;
                         }
+
+                        table
+                            .entry(right_row[right_column].clone())
+                            .or_default()
+                            .push(right_row);
+                    }
+                }
+
+                JoinAlgorithm::Hash { left_column, table }
+            }
+            None => JoinAlgorithm::NestedLoop {
+                on,
+                rows: right.by_ref().flat_map(batch_to_rows).collect(),
+            },
+        };
+
+        Self {
+            join_kind,
+            left,
+            left_rows: Vec::new().into_iter(),
+            left_attributes,
+            right_attributes,
+            algorithm,
+            current_left_row: None,
+            current_left_matched: false,
+            pending_right_rows: Vec::new().into_iter(),
+        }
+    }
+
+    fn next_left_row(&mut self) -> Option<Vec<Value>> {
+        loop {
+            if let Some(row) = self.left_rows.next() {
+                return Some(row);
+            }
+
+            let batch = self.left.next()?;
+            self.left_rows = batch_to_rows(batch).into_iter();
+        }
+    }
+
+    fn next_row(&mut self) -> Option<Vec<Value>> {
+        loop {
+            if let Some(right_row) = self.pending_right_rows.next() {
+                self.current_left_matched = true;
+
+                let mut combined = self
+                    .current_left_row
+                    .clone()
+                    .expect("pending right row without a current left row");
+                combined.extend(right_row);
+
+                return Some(combined);
+            }
+
+            if let Some(left_row) = self.current_left_row.take() {
+                if !self.current_left_matched && matches!(self.join_kind, JoinKind::Left) {
+                    let mut combined = left_row;
+                    combined.extend((0..self.right_attributes.len()).map(|_| Value::Null));
+
+                    return Some(combined);
+                }
+            }
+
+            let left_row = self.next_left_row()?;
+            self.current_left_matched = false;
+
+            self.pending_right_rows = match &self.algorithm {
+                JoinAlgorithm::Hash { left_column, table } => {
+                    if matches!(left_row[*left_column], Value::Null) {
+                        Vec::new().into_iter()
                     } else {
-                        let select_item_name = match select_item {
-                            SelectItem::ExprWithAlias { alias, .. } => alias.value.clone(),
-                            SelectItem::UnnamedExpr(expr) => match expr {
-                                Expr::Identifier(ident) => ident.value.clone(),
-                                _ => unreachable!(),
-                            },
-                            _ => unimplemented!(),
-                        };
-
-                        let source_position = relation_attributes
-                            .iter()
-                            .position(|relation_attribute| relation_attribute.eq(&select_item_name))
-                            .unwrap();
-
-                        item.push(relation_item.index(source_position).clone());
+                        table
+                            .get(&left_row[*left_column])
+                            .cloned()
+                            .unwrap_or_default()
+                            .into_iter()
                     }
                 }
+                JoinAlgorithm::NestedLoop { on, rows } => {
+                    let mut combined_attributes = self.left_attributes.clone();
+                    combined_attributes.extend(self.right_attributes.clone());
+
+                    rows.iter()
+                        .filter(|right_row| {
+                            let mut combined_row = left_row.clone();
+                            combined_row.extend((*right_row).clone());
 
-                Some(item)
+                            eval_value_as_bool(eval_expr_on_row(
+                                on.clone(),
+                                &combined_attributes,
+                                &combined_row,
+                            ))
+                        })
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .into_iter()
+                }
+            };
+
+            self.current_left_row = Some(left_row);
+        }
+    }
+}
+
+impl Iterator for Join {
+    type Item = RecordBatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut rows = Vec::new();
+
+        while rows.len() < BATCH_SIZE {
+            match self.next_row() {
+                Some(row) => rows.push(row),
+                None => break,
             }
-            None => None,
         }
+
+        if rows.is_empty() {
+            return None;
+        }
+
+        Some(rows_to_batch(rows))
     }
 }
 
-impl Relation for Projection {
-    fn attributes(&mut self) -> Vec<String> {
-        let mut attributes: Vec<String> = Vec::new();
+impl Relation for Join {
+    fn attributes(&mut self) -> Vec<Attribute> {
+        let mut attributes = self.left_attributes.clone();
+        attributes.extend(self.right_attributes.clone());
 
-        for select_item in self.projected.iter() {
-            match select_item {
-                SelectItem::ExprWithAlias { alias, .. } => {
-                    attributes.push(alias.value.clone());
+        attributes
+    }
+}
+
+/// An identifier as written in the query: a bare column name, or one qualified by the table
+/// segment immediately before it (`orders.id` -> table `orders`, name `id`; deeper schema-level
+/// qualifiers aren't modeled, only the last two segments matter).
+struct ParsedIdentifier {
+    table: Option<String>,
+    name: String,
+}
+
+fn parse_identifier(expr: &Expr) -> Option<ParsedIdentifier> {
+    match expr {
+        Expr::Identifier(ident) => Some(ParsedIdentifier {
+            table: None,
+            name: ident.value.clone(),
+        }),
+        Expr::CompoundIdentifier(idents) => {
+            let name = idents.last()?.value.clone();
+            let table = (idents.len() >= 2).then(|| idents[idents.len() - 2].value.clone());
+
+            Some(ParsedIdentifier { table, name })
+        }
+        _ => None,
+    }
+}
+
+/// Resolves a parsed identifier to a row index into `attributes`. A table-qualified identifier
+/// only matches a column tagged with that exact table, so e.g. `orders.id` can't accidentally
+/// resolve to `users.id` just because it comes first in a post-JOIN attribute list. A bare
+/// identifier matches the first column with that name, same as before qualification existed.
+fn resolve_attribute(parsed: &ParsedIdentifier, attributes: &Vec<Attribute>) -> usize {
+    match &parsed.table {
+        Some(table) => attributes
+            .iter()
+            .position(|attribute| {
+                attribute.name == parsed.name && attribute.table.as_deref() == Some(table.as_str())
+            })
+            .unwrap_or_else(|| panic!("Unknown column: {table}.{}", parsed.name)),
+        None => attributes
+            .iter()
+            .position(|attribute| attribute.name == parsed.name)
+            .unwrap_or_else(|| panic!("Unknown column: {}", parsed.name)),
+    }
+}
+
+/// If `on` is a single equality between one identifier from each side, returns
+/// `(left_column, right_column)` as row indices into the respective sides so the hash-join path
+/// can be used. Anything else (multiple predicates, non-equality, computed expressions) falls
+/// back to the nested-loop path.
+fn equality_join_columns(
+    on: &Expr,
+    left_attributes: &Vec<Attribute>,
+    right_attributes: &Vec<Attribute>,
+) -> Option<(usize, usize)> {
+    match on {
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::Eq,
+            right,
+        } => {
+            let left_ident = parse_identifier(left)?;
+            let right_ident = parse_identifier(right)?;
+
+            // A table qualifier, when present, must match the column's table, as in
+            // `resolve_attribute`.
+            let position = |attributes: &Vec<Attribute>, ident: &ParsedIdentifier| {
+                attributes.iter().position(|a| {
+                    a.name == ident.name
+                        && ident.table.as_ref().map_or(true, |t| a.table.as_ref() == Some(t))
+                })
+            };
+
+            if let (Some(l), Some(r)) = (
+                position(left_attributes, &left_ident),
+                position(right_attributes, &right_ident),
+            ) {
+                return Some((l, r));
+            }
+
+            if let (Some(l), Some(r)) = (
+                position(left_attributes, &right_ident),
+                position(right_attributes, &left_ident),
+            ) {
+                return Some((l, r));
+            }
+
+            None
+        }
+        _ => None,
+    }
+}
+
+fn table_factor_as_relation(table_factor: &TableFactor) -> Box<dyn Relation> {
+    match table_factor {
+        TableFactor::Table { name, alias, .. } => {
+            let filename = name
+                .0
+                .iter()
+                .map(|ident| ident.value.clone())
+                .collect::<Vec<String>>()
+                .join(".");
+
+            // A qualified lookup like `orders.id` must resolve against whatever name the query
+            // used to refer to this table, which is the alias when one is given, not the base name.
+            let table_name = alias
+                .as_ref()
+                .map(|alias| alias.name.value.clone())
+                .unwrap_or_else(|| filename.clone());
+
+            Box::new(SequentialScan::from_path(&filename, table_name))
+        }
+        _ => {
+            unimplemented!()
+        }
+    }
+}
+
+fn joined_relation(
+    table_with_joins: &TableWithJoins,
+    mut relation: Box<dyn Relation>,
+) -> Box<dyn Relation> {
+    for join in &table_with_joins.joins {
+        let (join_kind, constraint) = match &join.join_operator {
+            JoinOperator::Inner(constraint) => (JoinKind::Inner, constraint),
+            JoinOperator::LeftOuter(constraint) => (JoinKind::Left, constraint),
+            _ => unimplemented!("Only INNER and LEFT JOIN are supported."),
+        };
+
+        let on = match constraint {
+            JoinConstraint::On(expr) => expr.clone(),
+            _ => unimplemented!("Only ON-based JOIN conditions are supported."),
+        };
+
+        let right = table_factor_as_relation(&join.relation);
+
+        relation = Box::new(Join::new(join_kind, on, relation, right));
+    }
+
+    relation
+}
+
+/// `Sum`/`Avg` accumulate into `f64` regardless of input type, so a `Float` cell is never
+/// silently dropped; `saw_float` records whether any `Float` was actually seen, so `finalize`
+/// can still report an `Integer` result (the `f64` sum cast back to `i64`) for all-integer
+/// columns; that result is exact only while the sum fits in `f64`'s 53-bit mantissa.
+enum Accumulator {
+    Count { count: i64 },
+    Sum { sum: f64, saw_float: bool },
+    Avg { sum: f64, count: i64, saw_float: bool },
+    Min { value: Option<Value> },
+    Max { value: Option<Value> },
+}
+
+impl Accumulator {
+    fn new(function_name: &str) -> Self {
+        match function_name.to_uppercase().as_str() {
+            "COUNT" => Accumulator::Count { count: 0 },
+            "SUM" => Accumulator::Sum { sum: 0.0, saw_float: false },
+            "AVG" => Accumulator::Avg { sum: 0.0, count: 0, saw_float: false },
+            "MIN" => Accumulator::Min { value: None },
+            "MAX" => Accumulator::Max { value: None },
+            _ => unimplemented!("Unsupported aggregate function: {function_name}"),
+        }
+    }
+
+    fn accumulate(&mut self, value: Option<Value>) {
+        match self {
+            Accumulator::Count { count } => *count += 1,
+            Accumulator::Sum { sum, saw_float } => match value {
+                Some(Value::Integer(i)) => *sum += i as f64,
+                Some(Value::Float(f)) => {
+                    *sum += f;
+                    *saw_float = true;
                 }
-                SelectItem::UnnamedExpr(expr) => match expr {
-                    Expr::Identifier(ident) => {
-                        attributes.push(ident.value.clone());
+                _ => {}
+            },
+            Accumulator::Avg { sum, count, saw_float } => match value {
+                Some(Value::Integer(i)) => {
+                    *sum += i as f64;
+                    *count += 1;
+                }
+                Some(Value::Float(f)) => {
+                    *sum += f;
+                    *count += 1;
+                    *saw_float = true;
+                }
+                _ => {}
+            },
+            Accumulator::Min { value: current } => {
+                if let Some(value) = value {
+                    let is_smaller = match current {
+                        // `compare_values` alone has no Integer/Float arm and silently treats
+                        // cross-type pairs as equal; `compare_values_for_sort` promotes first, so
+                        // a batch boundary that flips a numeric column's type (Ints vs Floats)
+                        // can't hide the true extreme.
+                        Some(existing) => compare_values_for_sort(&value, existing).is_lt(),
+                        None => true,
+                    };
+
+                    if is_smaller {
+                        *current = Some(value);
                     }
-                    _ => unimplemented!(),
-                },
-                SelectItem::Wildcard => {
-                    for attribute in self.relation.attributes() {
-                        attributes.push(attribute);
+                }
+            }
+            Accumulator::Max { value: current } => {
+                if let Some(value) = value {
+                    let is_larger = match current {
+                        Some(existing) => compare_values_for_sort(&value, existing).is_gt(),
+                        None => true,
+                    };
+
+                    if is_larger {
+                        *current = Some(value);
                     }
                 }
-                _ => unimplemented!(),
             }
         }
+    }
 
-        attributes
+    fn finalize(&self) -> Value {
+        match self {
+            Accumulator::Count { count } => Value::Integer(*count),
+            Accumulator::Sum { sum, saw_float } => {
+                if *saw_float {
+                    Value::Float(*sum)
+                } else {
+                    Value::Integer(*sum as i64)
+                }
+            }
+            Accumulator::Avg { sum, count, saw_float } => {
+                if *count == 0 {
+                    Value::Integer(0)
+                } else if *saw_float {
+                    Value::Float(sum / *count as f64)
+                } else {
+                    Value::Integer(*sum as i64 / count)
+                }
+            }
+            Accumulator::Min { value } => value.clone().unwrap_or(Value::Null),
+            Accumulator::Max { value } => value.clone().unwrap_or(Value::Null),
+        }
     }
 }
 
-struct Selection {
-    selection: Expr,
-    relation: Box<dyn Relation<Item = Vec<Value>>>,
+fn compare_values(a: &Value, b: &Value) -> std::cmp::Ordering {
+    match (a, b) {
+        (Value::Integer(x), Value::Integer(y)) => x.cmp(y),
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Boolean(x), Value::Boolean(y)) => x.cmp(y),
+        (Value::Float(x), Value::Float(y)) => x.partial_cmp(y).unwrap_or(std::cmp::Ordering::Equal),
+        _ => std::cmp::Ordering::Equal,
+    }
 }
 
-impl Iterator for Selection {
-    type Item = Vec<Value>;
+struct AggregateSpec {
+    function_name: String,
+    arg: Option<Expr>,
+    output_name: String,
+}
+
+fn function_arg_expr(function: &Function) -> Option<Expr> {
+    match function.args.first() {
+        Some(FunctionArg::Unnamed(FunctionArgExpr::Expr(expr))) => Some(expr.clone()),
+        Some(FunctionArg::Unnamed(FunctionArgExpr::Wildcard)) => None,
+        None => None,
+        _ => unimplemented!("Unsupported aggregate argument."),
+    }
+}
+
+fn aggregate_spec(select_item: &SelectItem) -> Option<AggregateSpec> {
+    match select_item {
+        SelectItem::ExprWithAlias {
+            expr: Expr::Function(function),
+            alias,
+        } => Some(AggregateSpec {
+            function_name: function.name.0.last().unwrap().value.clone(),
+            arg: function_arg_expr(function),
+            output_name: alias.value.clone(),
+        }),
+        SelectItem::UnnamedExpr(Expr::Function(function)) => {
+            let function_name = function.name.0.last().unwrap().value.clone();
+
+            Some(AggregateSpec {
+                arg: function_arg_expr(function),
+                output_name: function_name.to_lowercase(),
+                function_name,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn is_aggregate_select_item(select_item: &SelectItem) -> bool {
+    matches!(
+        select_item,
+        SelectItem::UnnamedExpr(Expr::Function(_))
+            | SelectItem::ExprWithAlias {
+                expr: Expr::Function(_),
+                ..
+            }
+    )
+}
+
+struct Aggregation {
+    group_by: Vec<Expr>,
+    group_by_names: Vec<String>,
+    aggregates: Vec<AggregateSpec>,
+    child_attributes: Vec<Attribute>,
+    relation: Box<dyn Relation>,
+    rows: Option<std::vec::IntoIter<Vec<Value>>>,
+}
+
+impl Aggregation {
+    pub fn new(
+        group_by: Vec<Expr>,
+        projection: Vec<SelectItem>,
+        mut relation: Box<dyn Relation>,
+    ) -> Self {
+        let child_attributes = relation.attributes();
+
+        let group_by_names = group_by
+            .iter()
+            .map(|expr| match expr {
+                Expr::Identifier(ident) => ident.value.clone(),
+                _ => unimplemented!("Only identifier GROUP BY expressions are supported."),
+            })
+            .collect();
+
+        let aggregates = projection
+            .iter()
+            .filter_map(aggregate_spec)
+            .collect();
+
+        Self {
+            group_by,
+            group_by_names,
+            aggregates,
+            child_attributes,
+            relation,
+            rows: None,
+        }
+    }
+
+    fn new_accumulators(&self) -> Vec<Accumulator> {
+        self.aggregates
+            .iter()
+            .map(|aggregate| Accumulator::new(&aggregate.function_name))
+            .collect()
+    }
+
+    fn ensure_computed(&mut self) {
+        if self.rows.is_some() {
+            return;
+        }
+
+        let mut groups: HashMap<Vec<Value>, Vec<Accumulator>> = HashMap::new();
+
+        if self.group_by.is_empty() {
+            groups.insert(Vec::new(), self.new_accumulators());
+        }
+
+        while let Some(batch) = self.relation.next() {
+            for row in batch_to_rows(batch) {
+                let key: Vec<Value> = self
+                    .group_by
+                    .iter()
+                    .map(|expr| eval_expr_on_row(expr.clone(), &self.child_attributes, &row))
+                    .collect();
+
+                let accumulators = groups
+                    .entry(key)
+                    .or_insert_with(|| self.new_accumulators());
+
+                for (accumulator, aggregate) in accumulators.iter_mut().zip(self.aggregates.iter())
+                {
+                    let value = aggregate
+                        .arg
+                        .as_ref()
+                        .map(|expr| eval_expr_on_row(expr.clone(), &self.child_attributes, &row));
+
+                    accumulator.accumulate(value);
+                }
+            }
+        }
+
+        let rows = groups
+            .into_iter()
+            .map(|(key, accumulators)| {
+                let mut row = key;
+                row.extend(accumulators.iter().map(Accumulator::finalize));
+                row
+            })
+            .collect::<Vec<_>>();
+
+        self.rows = Some(rows.into_iter());
+    }
+}
+
+impl Iterator for Aggregation {
+    type Item = RecordBatch;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut item: Option<Self::Item> = None;
+        self.ensure_computed();
 
-        loop {
-            match self.relation.next() {
+        let rows_iter = self.rows.as_mut().unwrap();
+        let mut rows = Vec::new();
+
+        while rows.len() < BATCH_SIZE {
+            match rows_iter.next() {
+                Some(row) => rows.push(row),
                 None => break,
-                Some(relation_item) => {
-                    // TODO: "compile" selection expr into callable and cache it.
+            }
+        }
 
-                    if !eval_value_as_bool(eval_expr_on_row(self.selection.clone(), &self.relation.attributes(), &relation_item))
-                    {
-                        continue;
-                    }
+        if rows.is_empty() {
+            return None;
+        }
 
-                    item = Some(relation_item);
-                    break;
+        Some(rows_to_batch(rows))
+    }
+}
+
+impl Relation for Aggregation {
+    fn attributes(&mut self) -> Vec<Attribute> {
+        let mut attributes: Vec<Attribute> = self
+            .group_by_names
+            .iter()
+            .map(|name| Attribute {
+                table: None,
+                name: name.clone(),
+            })
+            .collect();
+
+        attributes.extend(self.aggregates.iter().map(|aggregate| Attribute {
+            table: None,
+            name: aggregate.output_name.clone(),
+        }));
+
+        attributes
+    }
+}
+
+/// Like `compare_values`, but gives NULLs a defined place (sorted before every other value) and
+/// promotes Integer/Float across each other, so `ORDER BY` yields a consistent order over a column
+/// whose values are all of one type or all numeric. Values of other differing types (e.g. a
+/// `String` against an `Integer`) compare as equal, as does a NaN float against any other float.
+fn compare_values_for_sort(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Null, Value::Null) => Ordering::Equal,
+        (Value::Null, _) => Ordering::Less,
+        (_, Value::Null) => Ordering::Greater,
+        _ => {
+            let (a, b) = promote_numeric(a.clone(), b.clone());
+
+            compare_values(&a, &b)
+        }
+    }
+}
+
+struct Sort {
+    keys: Vec<(usize, bool)>,
+    child_attributes: Vec<Attribute>,
+    relation: Box<dyn Relation>,
+    rows: Option<std::vec::IntoIter<Vec<Value>>>,
+}
+
+impl Sort {
+    pub fn new(order_by: &Vec<OrderByExpr>, mut relation: Box<dyn Relation>) -> Self {
+        let child_attributes = relation.attributes();
+
+        let keys = order_by
+            .iter()
+            .map(|order_by_expr| {
+                let parsed = parse_identifier(&order_by_expr.expr)
+                    .unwrap_or_else(|| unimplemented!("Only identifier ORDER BY expressions are supported."));
+                let source_position = resolve_attribute(&parsed, &child_attributes);
+
+                (source_position, order_by_expr.asc.unwrap_or(true))
+            })
+            .collect();
+
+        Self {
+            keys,
+            child_attributes,
+            relation,
+            rows: None,
+        }
+    }
+
+    fn ensure_computed(&mut self) {
+        if self.rows.is_some() {
+            return;
+        }
+
+        let mut rows = Vec::new();
+
+        while let Some(batch) = self.relation.next() {
+            rows.extend(batch_to_rows(batch));
+        }
+
+        rows.sort_by(|a, b| {
+            for (column_index, ascending) in &self.keys {
+                let ordering = compare_values_for_sort(&a[*column_index], &b[*column_index]);
+                let ordering = if *ascending { ordering } else { ordering.reverse() };
+
+                if ordering != Ordering::Equal {
+                    return ordering;
                 }
             }
+
+            Ordering::Equal
+        });
+
+        self.rows = Some(rows.into_iter());
+    }
+}
+
+impl Iterator for Sort {
+    type Item = RecordBatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.ensure_computed();
+
+        let rows_iter = self.rows.as_mut().unwrap();
+        let mut rows = Vec::new();
+
+        while rows.len() < BATCH_SIZE {
+            match rows_iter.next() {
+                Some(row) => rows.push(row),
+                None => break,
+            }
+        }
+
+        if rows.is_empty() {
+            return None;
+        }
+
+        Some(rows_to_batch(rows))
+    }
+}
+
+impl Relation for Sort {
+    fn attributes(&mut self) -> Vec<Attribute> {
+        self.child_attributes.clone()
+    }
+}
+
+/// Skips `offset` rows then passes through at most `limit` rows, short-circuiting the child scan
+/// as soon as the limit is reached instead of materializing the whole relation like `Sort` does.
+struct Limit {
+    limit: Option<usize>,
+    offset: usize,
+    skipped: usize,
+    taken: usize,
+    child_attributes: Vec<Attribute>,
+    relation: Box<dyn Relation>,
+}
+
+impl Limit {
+    pub fn new(limit: Option<usize>, offset: usize, mut relation: Box<dyn Relation>) -> Self {
+        let child_attributes = relation.attributes();
+
+        Self {
+            limit,
+            offset,
+            skipped: 0,
+            taken: 0,
+            child_attributes,
+            relation,
+        }
+    }
+}
+
+impl Iterator for Limit {
+    type Item = RecordBatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(limit) = self.limit {
+            if self.taken >= limit {
+                return None;
+            }
         }
 
-        item
+        loop {
+            let mut rows = batch_to_rows(self.relation.next()?);
+
+            if self.skipped < self.offset {
+                let to_skip = (self.offset - self.skipped).min(rows.len());
+                rows.drain(0..to_skip);
+                self.skipped += to_skip;
+
+                if rows.is_empty() {
+                    continue;
+                }
+            }
+
+            if let Some(limit) = self.limit {
+                let remaining = limit - self.taken;
+
+                if rows.len() > remaining {
+                    rows.truncate(remaining);
+                }
+            }
+
+            self.taken += rows.len();
+
+            return Some(rows_to_batch(rows));
+        }
     }
 }
 
-impl Relation for Selection {
-    fn attributes(&mut self) -> Vec<String> {
-        self.relation.attributes()
+impl Relation for Limit {
+    fn attributes(&mut self) -> Vec<Attribute> {
+        self.child_attributes.clone()
     }
 }
 
-fn eval_expr_on_row(expr: Expr, relation_attributes: &Vec<String>, row: &Vec<Value>) -> Value {
+fn eval_expr_on_row(expr: Expr, relation_attributes: &Vec<Attribute>, row: &Vec<Value>) -> Value {
     match expr {
         Expr::BinaryOp { left, op, right } => {
             let left_value = eval_expr_on_row(*left, relation_attributes, row);
             let right_value = eval_expr_on_row(*right, relation_attributes, row);
 
-            match op {
-                BinaryOperator::And => Value::Boolean(
-                    eval_value_as_bool(left_value.into()) && eval_value_as_bool(right_value.into()),
-                ),
-                BinaryOperator::Or => Value::Boolean(
-                    eval_value_as_bool(left_value.into()) || eval_value_as_bool(right_value.into()),
-                ),
-                BinaryOperator::Gt => Value::Boolean(match left_value {
-                    Value::Integer(left_int) => {
-                        left_int
-                            > match right_value {
-                                Value::Integer(right_int) => right_int,
-                                _ => unimplemented!(),
-                            }
-                    }
-                    _ => unimplemented!(),
-                }),
-                _ => unimplemented!(),
-            }
+            eval_binary_op(left_value, &op, right_value)
         }
-        Expr::Identifier(ident) => {
-            let source_position = relation_attributes
-                .iter()
-                .position(|relation_attribute| relation_attribute.eq(&ident.value))
-                .unwrap();
+        Expr::Identifier(_) | Expr::CompoundIdentifier(_) => {
+            let parsed = parse_identifier(&expr).unwrap();
+            let source_position = resolve_attribute(&parsed, relation_attributes);
 
             (*row.index(source_position)).to_owned()
         }
-        Expr::Value(literal) => match literal {
-            Literal::Boolean(b) => Value::Boolean(b),
-            Literal::DoubleQuotedString(s) | Literal::SingleQuotedString(s) => Value::String(s),
-            Literal::Number(s, _) => {
-                Value::Integer(s.parse::<i64>().expect("Could not parse number into i64."))
-            }
-            _ => unimplemented!(),
-        },
+        Expr::Value(literal) => literal_to_value(&literal),
+        Expr::IsNull(inner) => {
+            Value::Boolean(matches!(eval_expr_on_row(*inner, relation_attributes, row), Value::Null))
+        }
+        Expr::IsNotNull(inner) => {
+            Value::Boolean(!matches!(eval_expr_on_row(*inner, relation_attributes, row), Value::Null))
+        }
         _ => unimplemented!("{expr:?}"),
     }
 }
@@ -240,44 +1420,65 @@ fn eval_value_as_bool(value: Value) -> bool {
     match value {
         Value::Boolean(b) => b,
         Value::Integer(i) => i != 0,
+        Value::Float(f) => f != 0.0,
         Value::String(s) => s.len() > 0,
+        Value::Null => false,
     }
 }
 
-fn query_as_relation(query: &Box<Query>) -> Box<dyn Relation<Item = Vec<Value>> + 'static> {
+fn expr_as_usize(expr: &Expr) -> usize {
+    match expr {
+        Expr::Value(Literal::Number(s, _)) => {
+            s.parse::<usize>().expect("Could not parse LIMIT/OFFSET as usize.")
+        }
+        _ => unimplemented!("Only numeric literal LIMIT/OFFSET expressions are supported."),
+    }
+}
+
+fn query_as_relation(query: &Box<Query>) -> Box<dyn Relation> {
     match query.body.as_ref() {
         SetExpr::Select(select) => {
             let table_with_joins = select.from.first().expect("FROM must be provided.");
 
-            if !table_with_joins.joins.is_empty() {
-                unimplemented!("JOIN is not supported.")
-            }
-
             let table_factor = &table_with_joins.relation;
 
             match table_factor {
-                TableFactor::Table { name, .. } => {
-                    let filename = name
-                        .0
-                        .iter()
-                        .map(|ident| ident.value.clone())
-                        .collect::<Vec<String>>()
-                        .join(".");
+                TableFactor::Table { .. } => {
+                    let mut relation: Box<dyn Relation> = table_factor_as_relation(table_factor);
 
-                    let mut relation: Box<dyn Relation<Item = Vec<Value>> + 'static> =
-                        Box::new(SequentialScan::from_path(&filename));
+                    relation = joined_relation(table_with_joins, relation);
 
                     if let Some(selection) = &select.selection {
-                        relation = Box::new(Selection {
-                            selection: selection.to_owned(),
-                            relation,
-                        });
+                        relation = Box::new(Selection::new(selection, relation));
                     }
 
-                    if !select.projection.is_empty() {
+                    if !select.group_by.is_empty()
+                        || select.projection.iter().any(is_aggregate_select_item)
+                    {
+                        relation = Box::new(Aggregation::new(
+                            select.group_by.clone(),
+                            select.projection.clone(),
+                            relation,
+                        ));
+                    } else if !select.projection.is_empty() {
                         relation = Box::new(project_relation(select.projection.clone(), relation));
                     }
 
+                    if !query.order_by.is_empty() {
+                        relation = Box::new(Sort::new(&query.order_by, relation));
+                    }
+
+                    if query.limit.is_some() || query.offset.is_some() {
+                        let limit = query.limit.as_ref().map(expr_as_usize);
+                        let offset = query
+                            .offset
+                            .as_ref()
+                            .map(|offset| expr_as_usize(&offset.value))
+                            .unwrap_or(0);
+
+                        relation = Box::new(Limit::new(limit, offset, relation));
+                    }
+
                     return relation;
                 }
                 _ => {
@@ -291,13 +1492,104 @@ fn query_as_relation(query: &Box<Query>) -> Box<dyn Relation<Item = Vec<Value>>
     }
 }
 
-fn project_relation(
-    projection: Vec<SelectItem>,
-    relation: Box<dyn Relation<Item = Vec<Value>>>,
-) -> Projection {
-    Projection {
-        projected: projection,
-        relation,
+fn project_relation(projection: Vec<SelectItem>, relation: Box<dyn Relation>) -> Projection {
+    Projection::new(projection, relation)
+}
+
+enum OutputFormat {
+    Csv,
+    Table,
+}
+
+/// `--format=table`/`--format=csv` wins over the `SQL_OUTPUT_FORMAT` environment variable, which
+/// in turn wins over the CSV default, since CSV is what pipes into other tools expect.
+fn output_format() -> OutputFormat {
+    let flag = env::args().find_map(|arg| {
+        arg.strip_prefix("--format=").map(str::to_owned)
+    });
+
+    let value = flag.or_else(|| env::var("SQL_OUTPUT_FORMAT").ok());
+
+    match value.as_deref() {
+        Some("table") => OutputFormat::Table,
+        _ => OutputFormat::Csv,
+    }
+}
+
+fn write_csv(attributes: Vec<String>, relation: Box<dyn Relation>) {
+    let mut writer = csv::Writer::from_writer(io::stdout());
+
+    writer
+        .write_record(&attributes)
+        .expect("Could not write CSV-header to STDOUT.");
+
+    for batch in relation {
+        for row_index in 0..batch.num_rows() {
+            let record = csv::StringRecord::from_iter(
+                batch
+                    .columns
+                    .iter()
+                    .map(|column| value_to_string(column.value_at(row_index))),
+            );
+
+            writer
+                .write_record(&record)
+                .expect("Could not write result to stdout.");
+        }
+    }
+}
+
+/// Unlike `write_csv`, this has to buffer every row before printing anything, since each column's
+/// width (and thus the header's own padding) isn't known until the widest value in it is known.
+fn write_table(attributes: Vec<String>, relation: Box<dyn Relation>) {
+    let mut rows: Vec<Vec<String>> = Vec::new();
+
+    for batch in relation {
+        for row_index in 0..batch.num_rows() {
+            rows.push(
+                batch
+                    .columns
+                    .iter()
+                    .map(|column| value_to_string(column.value_at(row_index)))
+                    .collect(),
+            );
+        }
+    }
+
+    let widths: Vec<usize> = attributes
+        .iter()
+        .enumerate()
+        .map(|(column_index, attribute)| {
+            rows.iter()
+                .map(|row| row[column_index].len())
+                .max()
+                .unwrap_or(0)
+                .max(attribute.len())
+        })
+        .collect();
+
+    let print_row = |cells: &[String]| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+
+        println!("{}", cells.join(" | "));
+    };
+
+    print_row(&attributes);
+    println!(
+        "{}",
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("-+-")
+    );
+
+    for row in &rows {
+        print_row(row);
     }
 }
 
@@ -317,32 +1609,150 @@ fn main() {
         match statement {
             Statement::Query(query) => {
                 let mut relation = query_as_relation(query);
-                let attributes = relation.attributes();
-
-                let mut writer = csv::Writer::from_writer(io::stdout());
-
-                writer
-                    .write_record(attributes)
-                    .expect("Could not write CSV-header to STDOUT.");
-
-                for row in relation {
-                    let record = csv::StringRecord::from_iter(row.iter().map(|v| match v {
-                        Value::String(s) => s.to_owned(),
-                        Value::Boolean(b) => {
-                            if *b {
-                                "true".to_owned()
-                            } else {
-                                "false".to_owned()
-                            }
-                        }
-                        Value::Integer(i) => i.to_string(),
-                    }));
-                    writer
-                        .write_record(&record)
-                        .expect("Could not write result to stdout.");
+                let attributes: Vec<String> = relation
+                    .attributes()
+                    .into_iter()
+                    .map(|attribute| attribute.name)
+                    .collect();
+
+                match output_format() {
+                    OutputFormat::Csv => write_csv(attributes, relation),
+                    OutputFormat::Table => write_table(attributes, relation),
                 }
             }
             _ => unimplemented!(),
         }
     }
 }
+
+#[cfg(test)]
+struct VecRelation {
+    attributes: Vec<Attribute>,
+    batch: Option<RecordBatch>,
+}
+
+#[cfg(test)]
+impl VecRelation {
+    fn new(table: &str, attributes: Vec<&str>, rows: Vec<Vec<Value>>) -> Self {
+        Self {
+            attributes: attributes
+                .into_iter()
+                .map(|name| Attribute {
+                    table: Some(table.to_owned()),
+                    name: name.to_owned(),
+                })
+                .collect(),
+            batch: Some(rows_to_batch(rows)),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Iterator for VecRelation {
+    type Item = RecordBatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.batch.take()
+    }
+}
+
+#[cfg(test)]
+impl Relation for VecRelation {
+    fn attributes(&mut self) -> Vec<Attribute> {
+        self.attributes.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compound(table: &str, name: &str) -> Expr {
+        Expr::CompoundIdentifier(vec![
+            sqlparser::ast::Ident::new(table),
+            sqlparser::ast::Ident::new(name),
+        ])
+    }
+
+    #[test]
+    fn hash_join_does_not_match_null_keys_against_each_other() {
+        let left = VecRelation::new("left", vec!["id"], vec![vec![Value::Integer(1)], vec![Value::Null]]);
+        let right = VecRelation::new("right", vec!["id"], vec![vec![Value::Integer(1)], vec![Value::Null]]);
+
+        let on = Expr::BinaryOp {
+            left: Box::new(Expr::Identifier(sqlparser::ast::Ident::new("id"))),
+            op: BinaryOperator::Eq,
+            right: Box::new(Expr::Identifier(sqlparser::ast::Ident::new("id"))),
+        };
+
+        let join = Join::new(JoinKind::Inner, on, Box::new(left), Box::new(right));
+        let rows: Vec<Vec<Value>> = join.flat_map(batch_to_rows).collect();
+
+        assert_eq!(rows.len(), 1);
+    }
+
+    #[test]
+    fn qualified_columns_resolve_to_their_own_side_of_a_join_not_the_first_matching_name() {
+        let users = VecRelation::new(
+            "users",
+            vec!["id"],
+            vec![vec![Value::Integer(1)], vec![Value::Integer(2)]],
+        );
+        let orders = VecRelation::new(
+            "orders",
+            vec!["id", "user_id"],
+            vec![
+                vec![Value::Integer(1), Value::Integer(1)],
+                vec![Value::Integer(2), Value::Integer(1)],
+                vec![Value::Integer(3), Value::Integer(2)],
+            ],
+        );
+
+        let on = Expr::BinaryOp {
+            left: Box::new(compound("users", "id")),
+            op: BinaryOperator::Eq,
+            right: Box::new(compound("orders", "user_id")),
+        };
+
+        let join = Join::new(JoinKind::Inner, on, Box::new(users), Box::new(orders));
+        let mut relation: Box<dyn Relation> = Box::new(Projection::new(
+            vec![
+                SelectItem::UnnamedExpr(compound("users", "id")),
+                SelectItem::UnnamedExpr(compound("orders", "id")),
+            ],
+            Box::new(join),
+        ));
+
+        let rows: Vec<(i64, i64)> = batch_to_rows(relation.next().unwrap())
+            .into_iter()
+            .map(|row| match (&row[0], &row[1]) {
+                (Value::Integer(a), Value::Integer(b)) => (*a, *b),
+                _ => unreachable!(),
+            })
+            .collect();
+
+        assert_eq!(rows, vec![(1, 1), (1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn min_tracks_the_true_extreme_across_a_batch_boundary_that_changes_numeric_type() {
+        let mut min = Accumulator::new("MIN");
+
+        for _ in 0..1025 {
+            min.accumulate(Some(Value::Integer(1000)));
+        }
+        min.accumulate(Some(Value::Float(0.5)));
+
+        assert_eq!(value_to_string(min.finalize()), "0.5");
+    }
+
+    #[test]
+    fn max_tracks_the_true_extreme_across_integer_and_float_values() {
+        let mut max = Accumulator::new("MAX");
+
+        max.accumulate(Some(Value::Float(2.5)));
+        max.accumulate(Some(Value::Integer(3)));
+
+        assert_eq!(value_to_string(max.finalize()), "3");
+    }
+}